@@ -0,0 +1,85 @@
+use crate::sinks::{Healthcheck, VectorSink};
+use futures::future::BoxFuture;
+use once_cell::sync::OnceCell;
+
+mod sink_context;
+
+pub use sink_context::SinkContext;
+
+/// The event type(s) a source produces or a sink/transform accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    Log,
+    Metric,
+    Any,
+}
+
+/// Top-level config trait for a sink, registered via `inventory` under a unique name.
+#[typetag::serde(tag = "type")]
+pub trait SinkConfig: std::fmt::Debug + Send + Sync {
+    fn build(
+        &self,
+        cx: SinkContext,
+    ) -> BoxFuture<'static, crate::Result<(VectorSink, Healthcheck)>>;
+
+    fn input_type(&self) -> DataType;
+
+    fn sink_type(&self) -> &'static str;
+}
+
+/// Registers a `SinkConfig` implementation under the name used in `vector.toml`.
+pub struct SinkDescription {
+    pub name: &'static str,
+}
+
+impl SinkDescription {
+    pub const fn new<T>(name: &'static str) -> Self {
+        SinkDescription { name }
+    }
+}
+
+inventory::collect!(SinkDescription);
+
+/// Produces the default config for a component, used by docs generation and tests.
+pub trait GenerateConfig {
+    fn generate_config() -> toml::Value;
+}
+
+/// Field-name aliases that let a single `LogEvent` layout work across different
+/// sources (e.g. a source that calls its message field `msg` instead of `message`).
+#[derive(Debug, Clone)]
+pub struct LogSchema {
+    message_key: String,
+    timestamp_key: String,
+    host_key: String,
+}
+
+impl Default for LogSchema {
+    fn default() -> Self {
+        Self {
+            message_key: "message".into(),
+            timestamp_key: "timestamp".into(),
+            host_key: "host".into(),
+        }
+    }
+}
+
+impl LogSchema {
+    pub fn message_key(&self) -> &str {
+        &self.message_key
+    }
+
+    pub fn timestamp_key(&self) -> &str {
+        &self.timestamp_key
+    }
+
+    pub fn host_key(&self) -> &str {
+        &self.host_key
+    }
+}
+
+static LOG_SCHEMA: OnceCell<LogSchema> = OnceCell::new();
+
+pub fn log_schema() -> &'static LogSchema {
+    LOG_SCHEMA.get_or_init(LogSchema::default)
+}
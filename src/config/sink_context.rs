@@ -0,0 +1,9 @@
+/// Context handed to a sink at build time. It carries no fields.
+#[derive(Debug, Clone, Default)]
+pub struct SinkContext {}
+
+impl SinkContext {
+    pub fn new_test() -> Self {
+        SinkContext::default()
+    }
+}
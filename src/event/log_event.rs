@@ -0,0 +1,28 @@
+use super::Value;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A set of key/value fields, the payload of a `Log`-typed `Event`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LogEvent {
+    #[serde(flatten)]
+    fields: BTreeMap<String, Value>,
+}
+
+impl LogEvent {
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&Value> {
+        self.fields.get(key.as_ref())
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        self.fields.insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: impl AsRef<str>) -> Option<Value> {
+        self.fields.remove(key.as_ref())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.fields.keys()
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+mod log_event;
+mod value;
+
+pub use log_event::LogEvent;
+pub use value::Value;
+
+/// A single unit of data flowing through a pipeline. Only the `Log` variant exists
+/// today; `Metric` is intentionally left for a future addition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Log(LogEvent),
+}
+
+impl Event {
+    pub fn new_empty_log() -> Self {
+        Event::Log(LogEvent::default())
+    }
+
+    pub fn as_mut_log(&mut self) -> &mut LogEvent {
+        match self {
+            Event::Log(log) => log,
+        }
+    }
+
+    pub fn as_log(&self) -> &LogEvent {
+        match self {
+            Event::Log(log) => log,
+        }
+    }
+
+    pub fn into_log(self) -> LogEvent {
+        match self {
+            Event::Log(log) => log,
+        }
+    }
+}
+
+impl From<&str> for Event {
+    fn from(message: &str) -> Self {
+        let mut log = LogEvent::default();
+        log.insert(crate::config::log_schema().message_key(), message.to_string());
+        log.insert(
+            crate::config::log_schema().timestamp_key(),
+            Value::Timestamp(Utc::now()),
+        );
+        Event::Log(log)
+    }
+}
+
+impl From<String> for Event {
+    fn from(message: String) -> Self {
+        Event::from(message.as_str())
+    }
+}
+
+pub type LogEventFields = BTreeMap<String, Value>;
+
+pub type Timestamp = DateTime<Utc>;
@@ -0,0 +1,53 @@
+use super::Timestamp;
+use bytes::Bytes;
+use serde::{Serialize, Serializer};
+
+/// A single field's value within a `LogEvent`. Mirrors the handful of primitive
+/// shapes event sources and transforms actually produce.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bytes(Bytes),
+    Timestamp(Timestamp),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl Value {
+    pub fn to_string_lossy(&self) -> String {
+        match self {
+            Value::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+            Value::Timestamp(ts) => ts.to_rfc3339(),
+            Value::Integer(i) => i.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Boolean(b) => b.to_string(),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Bytes(Bytes::copy_from_slice(s.as_bytes()))
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Bytes(Bytes::from(s.into_bytes()))
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Bytes(_) => serializer.serialize_str(&self.to_string_lossy()),
+            Value::Timestamp(ts) => serializer.serialize_str(&ts.to_rfc3339()),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::Boolean(b) => serializer.serialize_bool(*b),
+        }
+    }
+}
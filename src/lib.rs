@@ -0,0 +1,12 @@
+pub mod config;
+pub mod event;
+pub mod sinks;
+pub mod tls;
+
+#[cfg(test)]
+pub mod test_util;
+
+pub use event::Event;
+
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+pub type Result<T, E = Error> = std::result::Result<T, E>;
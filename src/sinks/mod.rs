@@ -0,0 +1,29 @@
+pub mod papertrail;
+pub mod util;
+
+use crate::Event;
+use futures::future::BoxFuture;
+use tokio::sync::mpsc;
+
+/// A running sink: a handle to the background task(s) that drain the buffer and
+/// ship events out. Sending an event queues it; the task behind the channel owns
+/// encoding, connection management, and retries.
+pub struct VectorSink {
+    events: mpsc::UnboundedSender<Event>,
+}
+
+impl VectorSink {
+    pub(crate) fn new(events: mpsc::UnboundedSender<Event>) -> Self {
+        VectorSink { events }
+    }
+
+    /// Queues `event` for delivery. Only fails once the sink's background task
+    /// has exited, which only happens after the sender side is dropped.
+    pub fn send_event(&self, event: Event) -> Result<(), mpsc::error::SendError<Event>> {
+        self.events.send(event)
+    }
+}
+
+/// A probe run at startup (and surfaced to `vector validate --healthcheck`) that
+/// confirms the configured endpoint is actually reachable before traffic flows.
+pub type Healthcheck = BoxFuture<'static, crate::Result<()>>;
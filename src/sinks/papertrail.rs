@@ -2,13 +2,16 @@ use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
-        tcp::TcpSinkConfig,
-        Encoding, UriSerde,
+        disk_spool::DiskSpoolConfig,
+        tcp::{EncryptionConfig, ReconnectBackoffConfig, TcpSinkConfig},
+        Encoding, TransferEncoding, UriSerde,
     },
     tls::TlsConfig,
+    event::{LogEvent, Value},
     Event,
 };
 use bytes::Bytes;
+use chrono::SecondsFormat;
 use futures::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use syslog::{Facility, Formatter3164, LogFormat, Severity};
@@ -19,6 +22,237 @@ pub struct PapertrailConfig {
     endpoint: UriSerde,
     encoding: EncodingConfig<Encoding>,
     tls: Option<TlsConfig>,
+    #[serde(default)]
+    facility: SyslogFacility,
+    #[serde(default)]
+    severity: SeverityConfig,
+    /// Selects between legacy RFC 3164 framing and RFC 5424, which adds structured data.
+    #[serde(default, alias = "syslog_version")]
+    rfc: SyslogRfc,
+    /// The APP-NAME field of an RFC 5424 frame. Ignored under RFC 3164.
+    #[serde(default = "default_app_name")]
+    app_name: String,
+    /// The MSGID field of an RFC 5424 frame. Ignored under RFC 3164.
+    #[serde(default = "default_msgid")]
+    msgid: String,
+    /// Event fields to emit as RFC 5424 STRUCTURED-DATA elements. Ignored under RFC 3164.
+    #[serde(default)]
+    structured_data: Vec<StructuredDataConfig>,
+    /// How to make a binary `encoding.codec` (`message_pack`, `cbor`) safe to embed
+    /// in a syslog line. Required when `encoding.codec` is one of those.
+    #[serde(default)]
+    transfer_encoding: Option<TransferEncoding>,
+    /// Application-level ChaCha20-Poly1305 encryption of each frame, for endpoints
+    /// that don't terminate TLS. Composes with `tls` rather than replacing it.
+    #[serde(default)]
+    encryption: Option<EncryptionConfig>,
+    /// Reconnect backoff against `logs.papertrailapp.com`, to avoid a thundering-herd
+    /// reconnect storm against Papertrail during an upstream outage.
+    #[serde(default)]
+    reconnect_backoff: ReconnectBackoffConfig,
+    /// Spools encoded frames to disk while Papertrail is unreachable, replaying
+    /// non-expired entries in FIFO order ahead of new events once reconnected.
+    #[serde(default)]
+    disk_spool: Option<DiskSpoolConfig>,
+}
+
+fn default_app_name() -> String {
+    "vector".into()
+}
+
+fn default_msgid() -> String {
+    "-".into()
+}
+
+/// Which syslog protocol version frames are written in.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogRfc {
+    #[default]
+    Rfc3164,
+    Rfc5424,
+}
+
+/// A single RFC 5424 STRUCTURED-DATA element: `[id key="value" ...]`, with `fields`
+/// naming the event fields to pull params from (the event field name becomes the key).
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredDataConfig {
+    id: String,
+    fields: Vec<String>,
+}
+
+/// Escapes `]`, `"`, and `\` per RFC 5424's PARAM-VALUE grammar.
+fn escape_sd_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ']' | '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Builds the `STRUCTURED-DATA` portion of an RFC 5424 frame from `log`, which
+/// must be read before `encoding.apply_rules` strips any of the fields named
+/// in `elements` out of the event.
+fn build_structured_data(elements: &[StructuredDataConfig], log: &LogEvent) -> String {
+    if elements.is_empty() {
+        return "-".to_string();
+    }
+
+    elements
+        .iter()
+        .map(|sd| {
+            let kv_pairs = sd
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    log.get(field)
+                        .map(|v| format!(" {}=\"{}\"", field, escape_sd_value(&v.to_string_lossy())))
+                })
+                .collect::<String>();
+            format!("[{}{}]", sd.id, kv_pairs)
+        })
+        .collect::<String>()
+}
+
+/// The syslog facility written into every frame's PRI value, overridable per-sink.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogFacility {
+    Kern,
+    #[default]
+    User,
+    Mail,
+    Daemon,
+    Auth,
+    Syslog,
+    Lpr,
+    News,
+    Uucp,
+    Cron,
+    AuthPriv,
+    Ftp,
+    Local0,
+    Local1,
+    Local2,
+    Local3,
+    Local4,
+    Local5,
+    Local6,
+    Local7,
+}
+
+impl SyslogFacility {
+    fn as_facility(self) -> Facility {
+        match self {
+            SyslogFacility::Kern => Facility::LOG_KERN,
+            SyslogFacility::User => Facility::LOG_USER,
+            SyslogFacility::Mail => Facility::LOG_MAIL,
+            SyslogFacility::Daemon => Facility::LOG_DAEMON,
+            SyslogFacility::Auth => Facility::LOG_AUTH,
+            SyslogFacility::Syslog => Facility::LOG_SYSLOG,
+            SyslogFacility::Lpr => Facility::LOG_LPR,
+            SyslogFacility::News => Facility::LOG_NEWS,
+            SyslogFacility::Uucp => Facility::LOG_UUCP,
+            SyslogFacility::Cron => Facility::LOG_CRON,
+            SyslogFacility::AuthPriv => Facility::LOG_AUTHPRIV,
+            SyslogFacility::Ftp => Facility::LOG_FTP,
+            SyslogFacility::Local0 => Facility::LOG_LOCAL0,
+            SyslogFacility::Local1 => Facility::LOG_LOCAL1,
+            SyslogFacility::Local2 => Facility::LOG_LOCAL2,
+            SyslogFacility::Local3 => Facility::LOG_LOCAL3,
+            SyslogFacility::Local4 => Facility::LOG_LOCAL4,
+            SyslogFacility::Local5 => Facility::LOG_LOCAL5,
+            SyslogFacility::Local6 => Facility::LOG_LOCAL6,
+            SyslogFacility::Local7 => Facility::LOG_LOCAL7,
+        }
+    }
+}
+
+/// The syslog severity levels, in the same order as RFC 5424's numeric codes.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogSeverity {
+    Emerg,
+    Alert,
+    Crit,
+    Err,
+    Warning,
+    Notice,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl SyslogSeverity {
+    fn as_severity(self) -> Severity {
+        match self {
+            SyslogSeverity::Emerg => Severity::LOG_EMERG,
+            SyslogSeverity::Alert => Severity::LOG_ALERT,
+            SyslogSeverity::Crit => Severity::LOG_CRIT,
+            SyslogSeverity::Err => Severity::LOG_ERR,
+            SyslogSeverity::Warning => Severity::LOG_WARNING,
+            SyslogSeverity::Notice => Severity::LOG_NOTICE,
+            SyslogSeverity::Info => Severity::LOG_INFO,
+            SyslogSeverity::Debug => Severity::LOG_DEBUG,
+        }
+    }
+
+    /// Maps a level/severity event field onto a `SyslogSeverity`, accepting both the
+    /// common string spellings ("error", "warn"/"warning", "info", "debug", ...) and
+    /// the RFC 5424 numeric codes (0-7). Returns `None` for anything unrecognized.
+    fn from_value(value: &Value) -> Option<Self> {
+        let s = value.to_string_lossy();
+        match s.to_lowercase().as_str() {
+            "emerg" | "emergency" | "panic" | "0" => Some(SyslogSeverity::Emerg),
+            "alert" | "1" => Some(SyslogSeverity::Alert),
+            "crit" | "critical" | "2" => Some(SyslogSeverity::Crit),
+            "err" | "error" | "3" => Some(SyslogSeverity::Err),
+            "warn" | "warning" | "4" => Some(SyslogSeverity::Warning),
+            "notice" | "5" => Some(SyslogSeverity::Notice),
+            "info" | "informational" | "6" => Some(SyslogSeverity::Info),
+            "debug" | "trace" | "7" => Some(SyslogSeverity::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// Configures how an event's own severity is derived for the syslog PRI value,
+/// rather than always stamping every line as `SyslogSeverity::Info`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityConfig {
+    /// The event field to read the severity from, e.g. `level` or `severity`.
+    #[serde(default = "default_severity_field")]
+    field: String,
+    /// Used when `field` is absent from the event or holds an unrecognized value.
+    #[serde(default)]
+    default: SyslogSeverity,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        Self {
+            field: default_severity_field(),
+            default: SyslogSeverity::default(),
+        }
+    }
+}
+
+fn default_severity_field() -> String {
+    "severity".into()
+}
+
+impl SeverityConfig {
+    fn resolve(&self, log: &LogEvent) -> Severity {
+        log.get(&self.field)
+            .and_then(SyslogSeverity::from_value)
+            .unwrap_or(self.default)
+            .as_severity()
+    }
 }
 
 inventory::submit! {
@@ -56,11 +290,33 @@ impl SinkConfig for PapertrailConfig {
             let address = format!("{}:{}", host, port);
             let tls = Some(this.tls.unwrap_or_else(TlsConfig::enabled));
 
-            let pid = std::process::id();
-            let encoding = this.encoding;
+            if matches!(this.encoding.codec(), Encoding::MessagePack | Encoding::Cbor)
+                && this.transfer_encoding.is_none()
+            {
+                return Err(
+                    "`transfer_encoding` (base64 or hex) is required when `encoding.codec` \
+                     is `message_pack` or `cbor`"
+                        .into(),
+                );
+            }
 
-            let sink_config = TcpSinkConfig::new(address, tls);
-            sink_config.build(cx, move |event| encode_event(event, pid, &encoding))
+            let params = EncodeParams {
+                pid: std::process::id(),
+                facility: this.facility,
+                severity: this.severity,
+                rfc: this.rfc,
+                app_name: this.app_name,
+                msgid: this.msgid,
+                structured_data: this.structured_data,
+                transfer_encoding: this.transfer_encoding,
+                encoding: this.encoding,
+            };
+
+            let sink_config = TcpSinkConfig::new(address, tls)
+                .with_encryption(this.encryption)
+                .with_reconnect_backoff(this.reconnect_backoff)
+                .with_disk_spool(this.disk_spool);
+            sink_config.build(cx, move |event| encode_event(event, &params))
         })
     }
 
@@ -73,46 +329,136 @@ impl SinkConfig for PapertrailConfig {
     }
 }
 
-fn encode_event(mut event: Event, pid: u32, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
+/// Everything `encode_event` needs, bundled so `build` only has to move one value
+/// into the per-event closure handed to `TcpSinkConfig`.
+struct EncodeParams {
+    pid: u32,
+    facility: SyslogFacility,
+    severity: SeverityConfig,
+    rfc: SyslogRfc,
+    app_name: String,
+    msgid: String,
+    structured_data: Vec<StructuredDataConfig>,
+    transfer_encoding: Option<TransferEncoding>,
+    encoding: EncodingConfig<Encoding>,
+}
+
+fn encode_event(mut event: Event, params: &EncodeParams) -> Option<Bytes> {
     let host = if let Some(host) = event.as_mut_log().remove(log_schema().host_key()) {
         Some(host.to_string_lossy())
     } else {
         None
     };
 
-    let formatter = Formatter3164 {
-        facility: Facility::LOG_USER,
-        hostname: host,
-        process: "vector".into(),
-        pid: pid as i32,
-    };
+    // Read the severity and any RFC 5424 structured-data fields before
+    // `apply_rules` runs, so an `except_fields`/`only_fields` rule that strips
+    // one of them doesn't silently fall back to the default severity or drop
+    // the structured-data element -- the same reason `host` is extracted
+    // first above.
+    let severity = params.severity.resolve(event.as_log());
+    let structured_data = build_structured_data(&params.structured_data, event.as_log());
 
-    let mut s: Vec<u8> = Vec::new();
-
-    encoding.apply_rules(&mut event);
+    params.encoding.apply_rules(&mut event);
     let log = event.into_log();
 
-    let message = match encoding.codec() {
+    let message = match params.encoding.codec() {
         Encoding::Json => serde_json::to_string(&log).unwrap(),
         Encoding::Text => log
             .get(log_schema().message_key())
             .map(|v| v.to_string_lossy())
             .unwrap_or_default(),
+        Encoding::MessagePack => {
+            let bytes = rmp_serde::to_vec(&log).unwrap();
+            params
+                .transfer_encoding
+                .expect("validated at config build time")
+                .encode(&bytes)
+        }
+        Encoding::Cbor => {
+            let mut bytes = Vec::new();
+            serde_cbor::to_writer(&mut bytes, &log).unwrap();
+            params
+                .transfer_encoding
+                .expect("validated at config build time")
+                .encode(&bytes)
+        }
     };
 
-    formatter
-        .format(&mut s, Severity::LOG_INFO, message)
-        .unwrap();
+    // The newline delimiter is appended by `TcpSinkConfig` itself, after any
+    // encryption, so it always ends up outside an encrypted frame.
+    let s: Vec<u8> = match params.rfc {
+        SyslogRfc::Rfc3164 => {
+            let formatter = Formatter3164 {
+                facility: params.facility.as_facility(),
+                hostname: host,
+                process: "vector".into(),
+                pid: params.pid,
+            };
 
-    s.push(b'\n');
+            let mut s = Vec::new();
+            formatter.format(&mut s, severity, message).unwrap();
+            s
+        }
+        SyslogRfc::Rfc5424 => {
+            format_5424(params, host, severity, &log, structured_data, message).into_bytes()
+        }
+    };
 
     Some(Bytes::from(s))
 }
 
+/// Formats one RFC 5424 frame: `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`.
+fn format_5424(
+    params: &EncodeParams,
+    hostname: Option<String>,
+    severity: Severity,
+    log: &LogEvent,
+    structured_data: String,
+    message: String,
+) -> String {
+    let pri = params.facility.as_facility() as i32 | severity as i32;
+
+    let timestamp = match log.get(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(ts)) => ts.to_rfc3339_opts(SecondsFormat::Millis, true),
+        _ => chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+    };
+
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        pri,
+        timestamp,
+        hostname.as_deref().unwrap_or("-"),
+        params.app_name,
+        params.pid,
+        params.msgid,
+        structured_data,
+        message,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_params(codec: Encoding, rfc: SyslogRfc) -> EncodeParams {
+        EncodeParams {
+            pid: 0,
+            facility: SyslogFacility::default(),
+            severity: SeverityConfig::default(),
+            rfc,
+            app_name: default_app_name(),
+            msgid: default_msgid(),
+            structured_data: Vec::new(),
+            transfer_encoding: None,
+            encoding: EncodingConfig {
+                codec,
+                only_fields: None,
+                except_fields: None,
+                timestamp_format: None,
+            },
+        }
+    }
+
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<PapertrailConfig>();
@@ -123,21 +469,106 @@ mod tests {
         let mut evt = Event::from("vector");
         evt.as_mut_log().insert("magic", "key");
 
-        let bytes = encode_event(
-            evt,
-            0,
-            &EncodingConfig {
-                codec: Encoding::Json,
-                only_fields: None,
-                except_fields: Some(vec!["magic".into()]),
-                timestamp_format: None,
-            },
-        )
-        .unwrap();
+        let mut params = test_params(Encoding::Json, SyslogRfc::Rfc3164);
+        params.encoding.except_fields = Some(vec!["magic".into()]);
 
-        let msg =
-            bytes.slice(String::from_utf8_lossy(&bytes).find(": ").unwrap() + 2..bytes.len() - 1);
+        let bytes = encode_event(evt, &params).unwrap();
+
+        let msg = bytes.slice(String::from_utf8_lossy(&bytes).find(": ").unwrap() + 2..bytes.len());
         let value: serde_json::Value = serde_json::from_slice(&msg).unwrap();
         assert!(!value.as_object().unwrap().contains_key("magic"));
     }
+
+    #[test]
+    fn encode_event_maps_severity() {
+        let mut evt = Event::from("vector");
+        evt.as_mut_log().insert("severity", "warn");
+
+        let bytes = encode_event(evt, &test_params(Encoding::Text, SyslogRfc::Rfc3164)).unwrap();
+
+        // PRI = facility * 8 + severity; USER (1) * 8 + WARNING (4) = 12
+        assert!(String::from_utf8_lossy(&bytes).starts_with("<12>"));
+    }
+
+    #[test]
+    fn encode_event_falls_back_to_default_severity() {
+        let evt = Event::from("vector");
+
+        let bytes = encode_event(evt, &test_params(Encoding::Text, SyslogRfc::Rfc3164)).unwrap();
+
+        // PRI = facility * 8 + severity; USER (1) * 8 + INFO (6) = 14
+        assert!(String::from_utf8_lossy(&bytes).starts_with("<14>"));
+    }
+
+    #[test]
+    fn encode_event_severity_survives_except_fields() {
+        let mut evt = Event::from("vector");
+        evt.as_mut_log().insert("severity", "error");
+
+        let mut params = test_params(Encoding::Text, SyslogRfc::Rfc3164);
+        params.encoding.except_fields = Some(vec!["severity".into()]);
+
+        let bytes = encode_event(evt, &params).unwrap();
+
+        // PRI = facility * 8 + severity; USER (1) * 8 + ERR (3) = 11, not the
+        // default INFO (14) a post-`apply_rules` read would fall back to.
+        assert!(String::from_utf8_lossy(&bytes).starts_with("<11>"));
+    }
+
+    #[test]
+    fn encode_event_5424_structured_data_survives_except_fields() {
+        let mut evt = Event::from("vector");
+        evt.as_mut_log().insert("request_id", "abc-123");
+
+        let mut params = test_params(Encoding::Text, SyslogRfc::Rfc5424);
+        params.encoding.except_fields = Some(vec!["request_id".into()]);
+        params.structured_data.push(StructuredDataConfig {
+            id: "meta@32473".into(),
+            fields: vec!["request_id".into()],
+        });
+
+        let bytes = encode_event(evt, &params).unwrap();
+        let frame = String::from_utf8_lossy(&bytes);
+
+        assert!(frame.contains("[meta@32473 request_id=\"abc-123\"]"));
+    }
+
+    #[test]
+    fn encode_event_5424_includes_structured_data() {
+        let mut evt = Event::from("vector");
+        evt.as_mut_log().insert("request_id", "abc-123");
+
+        let mut params = test_params(Encoding::Text, SyslogRfc::Rfc5424);
+        params.structured_data.push(StructuredDataConfig {
+            id: "meta@32473".into(),
+            fields: vec!["request_id".into()],
+        });
+
+        let bytes = encode_event(evt, &params).unwrap();
+        let frame = String::from_utf8_lossy(&bytes);
+
+        assert!(frame.starts_with("<14>1 "));
+        assert!(frame.contains("[meta@32473 request_id=\"abc-123\"]"));
+        assert!(frame.ends_with("vector"));
+    }
+
+    #[test]
+    fn escape_sd_value_escapes_special_chars() {
+        assert_eq!(escape_sd_value(r#"a]b"c\d"#), r#"a\]b\"c\\d"#);
+    }
+
+    #[test]
+    fn encode_event_message_pack_is_transfer_encoded() {
+        let evt = Event::from("vector");
+
+        let mut params = test_params(Encoding::MessagePack, SyslogRfc::Rfc3164);
+        params.transfer_encoding = Some(TransferEncoding::Hex);
+
+        let bytes = encode_event(evt, &params).unwrap();
+        let frame = String::from_utf8_lossy(&bytes);
+
+        // The MessagePack bytes are hex-encoded, so the MSG portion must be ASCII hex.
+        let msg = frame[frame.find(": ").unwrap() + 2..].to_string();
+        assert!(msg.chars().all(|c| c.is_ascii_hexdigit()));
+    }
 }
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Spools encoded frames to disk when the endpoint is unreachable, so a prolonged
+/// outage doesn't silently drop events. Each entry is stamped with an `expires_at`
+/// and is skipped (and removed) once past its TTL, bounding the spool's lifetime
+/// independent of its size cap.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DiskSpoolConfig {
+    /// Directory the spool's frame files are written into.
+    pub directory: PathBuf,
+    /// Once the spool exceeds this many bytes, the oldest entries are evicted
+    /// first to make room for new ones.
+    #[serde(default = "default_max_size_bytes")]
+    pub max_size_bytes: u64,
+    /// How long a spooled frame is kept before it's considered undeliverable and
+    /// dropped on the next scan.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_max_size_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+/// A FIFO, TTL-evicting spool of frames backed by one file per entry on disk.
+/// Entries are named `<sequence>.frame` so directory listing order recovers
+/// FIFO order across restarts.
+pub struct DiskSpool {
+    directory: PathBuf,
+    max_size_bytes: u64,
+    ttl: Duration,
+    next_seq: u64,
+    size_bytes: u64,
+}
+
+impl DiskSpool {
+    pub fn open(config: &DiskSpoolConfig) -> crate::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+
+        let mut next_seq = 0;
+        let mut size_bytes = 0;
+        for (seq, path) in list_entries(&config.directory)? {
+            next_seq = next_seq.max(seq + 1);
+            size_bytes += fs::metadata(&path)?.len();
+        }
+
+        Ok(DiskSpool {
+            directory: config.directory.clone(),
+            max_size_bytes: config.max_size_bytes,
+            ttl: Duration::from_secs(config.ttl_secs),
+            next_seq,
+            size_bytes,
+        })
+    }
+
+    /// Appends `frame` to the spool, then evicts the oldest entries (oldest
+    /// first) until the spool is back under `max_size_bytes`. A single frame
+    /// larger than `max_size_bytes` can never fit even once everything else
+    /// is evicted, so it's rejected outright rather than silently written
+    /// and immediately evicted.
+    pub fn push(&mut self, frame: &[u8]) -> crate::Result<()> {
+        let expires_at = now_unix() + self.ttl.as_secs();
+        let mut contents = Vec::with_capacity(8 + frame.len());
+        contents.extend_from_slice(&expires_at.to_le_bytes());
+        contents.extend_from_slice(frame);
+
+        if contents.len() as u64 > self.max_size_bytes {
+            return Err("frame is larger than the spool's max_size_bytes".into());
+        }
+
+        let path = self.directory.join(format!("{:020}.frame", self.next_seq));
+        fs::write(&path, &contents)?;
+
+        self.next_seq += 1;
+        self.size_bytes += contents.len() as u64;
+
+        self.evict_over_capacity()
+    }
+
+    /// Removes and returns every non-expired frame in FIFO order, for replay
+    /// ahead of new events once the connection is reestablished. Expired
+    /// entries are dropped without being returned. A corrupt entry is logged,
+    /// dropped, and skipped rather than aborting the drain, so it can't
+    /// permanently wedge every entry queued behind it.
+    pub fn drain(&mut self) -> crate::Result<Vec<Vec<u8>>> {
+        let now = now_unix();
+        let mut frames = Vec::new();
+
+        for (_, path) in list_entries(&self.directory)? {
+            let contents = fs::read(&path)?;
+            self.size_bytes = self.size_bytes.saturating_sub(contents.len() as u64);
+            fs::remove_file(&path)?;
+
+            match parse_entry(&contents) {
+                Ok((expires_at, frame)) if expires_at > now => frames.push(frame),
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!(message = "Dropping corrupt spool entry.", %error);
+                }
+            }
+        }
+
+        Ok(frames)
+    }
+
+    fn evict_over_capacity(&mut self) -> crate::Result<()> {
+        let entries = list_entries(&self.directory)?;
+        for (_, path) in entries {
+            if self.size_bytes <= self.max_size_bytes {
+                break;
+            }
+            let len = fs::metadata(&path)?.len();
+            fs::remove_file(&path)?;
+            self.size_bytes = self.size_bytes.saturating_sub(len);
+        }
+        Ok(())
+    }
+}
+
+fn parse_entry(contents: &[u8]) -> crate::Result<(u64, Vec<u8>)> {
+    if contents.len() < 8 {
+        return Err("corrupt spool entry: shorter than its header".into());
+    }
+    let mut expires_at_bytes = [0u8; 8];
+    expires_at_bytes.copy_from_slice(&contents[..8]);
+    Ok((u64::from_le_bytes(expires_at_bytes), contents[8..].to_vec()))
+}
+
+/// Lists spool entry files in ascending sequence order.
+fn list_entries(directory: &Path) -> crate::Result<Vec<(u64, PathBuf)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let path = entry.path();
+        let seq = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(seq) = seq {
+            entries.push((seq, path));
+        }
+    }
+    entries.sort_by_key(|(seq, _)| *seq);
+    Ok(entries)
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(dir: &Path) -> DiskSpoolConfig {
+        DiskSpoolConfig {
+            directory: dir.to_path_buf(),
+            max_size_bytes: default_max_size_bytes(),
+            ttl_secs: default_ttl_secs(),
+        }
+    }
+
+    #[test]
+    fn replays_entries_in_fifo_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spool = DiskSpool::open(&test_config(dir.path())).unwrap();
+
+        spool.push(b"first").unwrap();
+        spool.push(b"second").unwrap();
+        spool.push(b"third").unwrap();
+
+        let frames = spool.drain().unwrap();
+        assert_eq!(frames, vec![b"first".to_vec(), b"second".to_vec(), b"third".to_vec()]);
+    }
+
+    #[test]
+    fn drain_is_empty_after_draining_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spool = DiskSpool::open(&test_config(dir.path())).unwrap();
+
+        spool.push(b"only").unwrap();
+        assert_eq!(spool.drain().unwrap().len(), 1);
+        assert!(spool.drain().unwrap().is_empty());
+    }
+
+    #[test]
+    fn drops_expired_entries_without_replaying_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(dir.path());
+        config.ttl_secs = 0;
+        let mut spool = DiskSpool::open(&config).unwrap();
+
+        spool.push(b"stale").unwrap();
+        std::thread::sleep(Duration::from_millis(1100));
+
+        assert!(spool.drain().unwrap().is_empty());
+    }
+
+    #[test]
+    fn evicts_oldest_entries_once_over_the_size_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(dir.path());
+        // Each entry is 8-byte header + 5-byte payload = 13 bytes, so the cap
+        // only ever leaves room for the single most recent entry.
+        config.max_size_bytes = 20;
+        let mut spool = DiskSpool::open(&config).unwrap();
+
+        spool.push(b"aaaaa").unwrap();
+        spool.push(b"bbbbb").unwrap();
+        spool.push(b"ccccc").unwrap();
+
+        let frames = spool.drain().unwrap();
+        assert_eq!(frames, vec![b"ccccc".to_vec()]);
+    }
+
+    #[test]
+    fn rejects_a_frame_larger_than_the_size_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(dir.path());
+        config.max_size_bytes = 10;
+        let mut spool = DiskSpool::open(&config).unwrap();
+
+        assert!(spool.push(b"too big for the cap").is_err());
+        assert!(spool.drain().unwrap().is_empty());
+    }
+
+    #[test]
+    fn drain_skips_a_corrupt_entry_but_still_returns_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut spool = DiskSpool::open(&config).unwrap();
+
+        spool.push(b"first").unwrap();
+        fs::write(dir.path().join(format!("{:020}.frame", spool.next_seq)), b"short").unwrap();
+        spool.next_seq += 1;
+        spool.push(b"third").unwrap();
+
+        let frames = spool.drain().unwrap();
+        assert_eq!(frames, vec![b"first".to_vec(), b"third".to_vec()]);
+    }
+
+    #[test]
+    fn reopening_the_spool_recovers_unreplayed_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+
+        let mut spool = DiskSpool::open(&config).unwrap();
+        spool.push(b"survives restart").unwrap();
+        drop(spool);
+
+        let mut reopened = DiskSpool::open(&config).unwrap();
+        assert_eq!(reopened.drain().unwrap(), vec![b"survives restart".to_vec()]);
+    }
+}
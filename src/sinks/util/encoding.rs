@@ -0,0 +1,86 @@
+use crate::{event::Value, Event};
+use serde::{Deserialize, Serialize};
+
+/// How a `Timestamp` field is rendered when serializing an event.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    Unix,
+    Rfc3339,
+}
+
+/// Shared codec/field-selection config reused across every sink that serializes
+/// a `LogEvent` before writing it out.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct EncodingConfig<E> {
+    pub codec: E,
+    pub only_fields: Option<Vec<String>>,
+    pub except_fields: Option<Vec<String>>,
+    pub timestamp_format: Option<TimestampFormat>,
+}
+
+/// Applies the `only_fields`/`except_fields` trimming rules and the
+/// `timestamp_format` rendering common to every `EncodingConfig`, independent
+/// of the concrete codec type.
+pub trait EncodingConfiguration {
+    type Codec;
+
+    fn codec(&self) -> &Self::Codec;
+    fn only_fields(&self) -> &Option<Vec<String>>;
+    fn except_fields(&self) -> &Option<Vec<String>>;
+    fn timestamp_format(&self) -> &Option<TimestampFormat>;
+
+    fn apply_rules(&self, event: &mut Event) {
+        let log = event.as_mut_log();
+
+        if let Some(only_fields) = self.only_fields() {
+            let to_remove: Vec<String> = log
+                .keys()
+                .filter(|field| !only_fields.contains(field))
+                .cloned()
+                .collect();
+            for field in to_remove {
+                log.remove(&field);
+            }
+        }
+
+        if let Some(except_fields) = self.except_fields() {
+            for field in except_fields {
+                log.remove(field);
+            }
+        }
+
+        if let Some(TimestampFormat::Unix) = self.timestamp_format() {
+            let unix: Vec<(String, i64)> = log
+                .keys()
+                .filter_map(|field| match log.get(field) {
+                    Some(Value::Timestamp(ts)) => Some((field.clone(), ts.timestamp())),
+                    _ => None,
+                })
+                .collect();
+            for (field, secs) in unix {
+                log.insert(field, Value::Integer(secs));
+            }
+        }
+    }
+}
+
+impl<E> EncodingConfiguration for EncodingConfig<E> {
+    type Codec = E;
+
+    fn codec(&self) -> &E {
+        &self.codec
+    }
+
+    fn only_fields(&self) -> &Option<Vec<String>> {
+        &self.only_fields
+    }
+
+    fn except_fields(&self) -> &Option<Vec<String>> {
+        &self.except_fields
+    }
+
+    fn timestamp_format(&self) -> &Option<TimestampFormat> {
+        &self.timestamp_format
+    }
+}
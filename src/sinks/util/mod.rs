@@ -0,0 +1,78 @@
+pub mod disk_spool;
+pub mod encoding;
+pub mod tcp;
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The wire codec used to serialize a `LogEvent` before a sink frames it for
+/// transport. `MessagePack` and `Cbor` produce non-UTF8 bytes, so sinks that embed
+/// the result inside a text-based protocol must pair them with a `TransferEncoding`.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Json,
+    Text,
+    MessagePack,
+    Cbor,
+}
+
+/// How a binary-encoded payload is made ASCII-safe to embed in a text-based frame.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferEncoding {
+    Base64,
+    Hex,
+}
+
+impl TransferEncoding {
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            TransferEncoding::Base64 => base64::encode(bytes),
+            TransferEncoding::Hex => hex::encode(bytes),
+        }
+    }
+}
+
+/// A `http::Uri` with `Serialize`/`Deserialize` support, for `endpoint`-style config fields.
+#[derive(Debug, Clone)]
+pub struct UriSerde {
+    uri: http::Uri,
+}
+
+impl UriSerde {
+    pub fn host(&self) -> Option<&str> {
+        self.uri.host()
+    }
+
+    pub fn port_u16(&self) -> Option<u16> {
+        self.uri.port_u16()
+    }
+}
+
+impl FromStr for UriSerde {
+    type Err = http::uri::InvalidUri;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(UriSerde { uri: s.parse()? })
+    }
+}
+
+impl<'de> Deserialize<'de> for UriSerde {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for UriSerde {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&self.uri)
+    }
+}
@@ -0,0 +1,577 @@
+use crate::{
+    config::SinkContext,
+    sinks::{
+        util::disk_spool::{DiskSpool, DiskSpoolConfig},
+        Healthcheck, VectorSink,
+    },
+    tls::TlsConfig,
+    Event,
+};
+use bytes::Bytes;
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::{io::AsyncWriteExt, net::TcpStream, sync::mpsc};
+
+/// Application-level AEAD encryption for a `TcpSinkConfig`, for endpoints that don't
+/// terminate TLS but still need confidentiality and tamper-detection on the wire.
+/// Composes with `tls` rather than replacing it.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// The 32-byte pre-shared key, hex-encoded.
+    pub key: Option<String>,
+    /// Path to a file containing the hex-encoded 32-byte key.
+    pub key_file: Option<PathBuf>,
+}
+
+impl EncryptionConfig {
+    fn load_key(&self) -> crate::Result<Key> {
+        let hex_key = match (&self.key, &self.key_file) {
+            (Some(key), None) => key.clone(),
+            (None, Some(path)) => std::fs::read_to_string(path)?.trim().to_string(),
+            _ => return Err("exactly one of `key` or `key_file` must be set".into()),
+        };
+
+        let bytes = hex::decode(hex_key)?;
+        if bytes.len() != 32 {
+            return Err("encryption key must be exactly 32 bytes".into());
+        }
+
+        Ok(*Key::from_slice(&bytes))
+    }
+
+    fn build(&self) -> crate::Result<FrameEncryptor> {
+        Ok(FrameEncryptor {
+            cipher: ChaCha20Poly1305::new(&self.load_key()?),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct FrameEncryptor {
+    cipher: ChaCha20Poly1305,
+}
+
+impl FrameEncryptor {
+    /// Encrypts `frame`, returning `nonce || ciphertext || tag`.
+    fn encrypt(&self, frame: &[u8]) -> Vec<u8> {
+        let mut nonce_bytes = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = self
+            .cipher
+            .encrypt(nonce, frame)
+            .expect("encryption with a fixed-size AEAD key/nonce cannot fail");
+
+        let mut out = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        out
+    }
+
+    /// Reverses `encrypt`: splits off the nonce, decrypts the remaining
+    /// ciphertext+tag, and returns the original plaintext frame.
+    #[cfg(test)]
+    fn decrypt(&self, payload: &[u8]) -> crate::Result<Vec<u8>> {
+        if payload.len() < 12 {
+            return Err("encrypted frame shorter than the nonce".into());
+        }
+        let (nonce_bytes, ciphertext) = payload.split_at(12);
+        let nonce = Nonce::from_slice(nonce_bytes);
+        self.cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| "failed to decrypt frame (bad key or corrupted data)".into())
+    }
+}
+
+/// Exponential-backoff reconnect settings, so a dropped connection doesn't either
+/// spin-retry immediately or pile every sink instance onto the same retry instant.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct ReconnectBackoffConfig {
+    #[serde(default = "default_initial_backoff_secs")]
+    initial_backoff_secs: f64,
+    #[serde(default = "default_max_backoff_secs")]
+    max_backoff_secs: f64,
+    #[serde(default = "default_backoff_multiplier")]
+    multiplier: f64,
+    /// Randomizes each delay within `[1 - jitter, 1 + jitter]` of its computed value.
+    #[serde(default = "default_jitter_factor")]
+    jitter_factor: f64,
+}
+
+fn default_initial_backoff_secs() -> f64 {
+    1.0
+}
+
+fn default_max_backoff_secs() -> f64 {
+    30.0
+}
+
+fn default_backoff_multiplier() -> f64 {
+    2.0
+}
+
+fn default_jitter_factor() -> f64 {
+    0.1
+}
+
+impl Default for ReconnectBackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff_secs: default_initial_backoff_secs(),
+            max_backoff_secs: default_max_backoff_secs(),
+            multiplier: default_backoff_multiplier(),
+            jitter_factor: default_jitter_factor(),
+        }
+    }
+}
+
+impl ReconnectBackoffConfig {
+    /// `min(max_delay, initial * multiplier^attempt)`, perturbed by jitter.
+    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let base = self.initial_backoff_secs * self.multiplier.powi(attempt as i32);
+        let capped = base.min(self.max_backoff_secs).max(0.0);
+
+        let jitter = self.jitter_factor.clamp(0.0, 1.0);
+        let factor = rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter));
+
+        Duration::from_secs_f64((capped * factor).max(0.0))
+    }
+}
+
+/// Tracks reconnect attempts against a `ReconnectBackoffConfig`, resetting once a
+/// connection succeeds.
+struct ReconnectState {
+    backoff: ReconnectBackoffConfig,
+    attempt: u32,
+}
+
+impl ReconnectState {
+    fn new(backoff: ReconnectBackoffConfig) -> Self {
+        Self { backoff, attempt: 0 }
+    }
+
+    /// The delay to sleep before the next connect attempt, advancing the counter.
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.backoff.delay_for_attempt(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Called after a successful connection so the next failure starts from scratch.
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// Shared config for sinks that frame encoded events over a newline-delimited
+/// TCP (optionally TLS) connection, e.g. `papertrail`.
+#[derive(Debug, Clone)]
+pub struct TcpSinkConfig {
+    address: String,
+    tls: Option<TlsConfig>,
+    encryption: Option<EncryptionConfig>,
+    reconnect_backoff: ReconnectBackoffConfig,
+    disk_spool: Option<DiskSpoolConfig>,
+}
+
+impl TcpSinkConfig {
+    pub fn new(address: String, tls: Option<TlsConfig>) -> Self {
+        TcpSinkConfig {
+            address,
+            tls,
+            encryption: None,
+            reconnect_backoff: ReconnectBackoffConfig::default(),
+            disk_spool: None,
+        }
+    }
+
+    pub fn with_encryption(mut self, encryption: Option<EncryptionConfig>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    pub fn with_reconnect_backoff(mut self, reconnect_backoff: ReconnectBackoffConfig) -> Self {
+        self.reconnect_backoff = reconnect_backoff;
+        self
+    }
+
+    /// Enables spooling encoded frames to disk while the endpoint is unreachable,
+    /// replayed in FIFO order (ahead of new events) once reconnected.
+    pub fn with_disk_spool(mut self, disk_spool: Option<DiskSpoolConfig>) -> Self {
+        self.disk_spool = disk_spool;
+        self
+    }
+
+    /// Builds the sink, encoding each event with `encode_event`, optionally
+    /// encrypting the resulting frame, then appending the newline delimiter
+    /// before it's written to the connection.
+    pub fn build(
+        &self,
+        _cx: SinkContext,
+        encode_event: impl Fn(Event) -> Option<Bytes> + Send + Sync + 'static,
+    ) -> crate::Result<(VectorSink, Healthcheck)> {
+        let address = self.address.clone();
+        let tls = self
+            .tls
+            .as_ref()
+            .map(TlsConfig::connector)
+            .transpose()?
+            .flatten();
+        let encryptor = self
+            .encryption
+            .as_ref()
+            .map(EncryptionConfig::build)
+            .transpose()?;
+        let spool = self.disk_spool.as_ref().map(DiskSpool::open).transpose()?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(
+            address.clone(),
+            tls.clone(),
+            rx,
+            encode_event,
+            encryptor,
+            self.reconnect_backoff,
+            spool,
+        ));
+
+        let healthcheck = Box::pin(async move {
+            connect(&address, tls.as_ref()).await?;
+            Ok(())
+        });
+
+        Ok((VectorSink::new(tx), healthcheck))
+    }
+}
+
+/// A connected socket, either plaintext or wrapped in a negotiated TLS session.
+enum MaybeTlsStream {
+    Tcp(TcpStream),
+    Tls(tokio_native_tls::TlsStream<TcpStream>),
+}
+
+impl MaybeTlsStream {
+    async fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        match self {
+            MaybeTlsStream::Tcp(stream) => stream.write_all(buf).await,
+            MaybeTlsStream::Tls(stream) => stream.write_all(buf).await,
+        }
+    }
+}
+
+/// The hostname portion of an `address` of the form `host:port`, for TLS SNI
+/// and certificate verification.
+fn address_domain(address: &str) -> &str {
+    address.rsplit_once(':').map_or(address, |(host, _)| host)
+}
+
+/// Opens a TCP connection to `address`, negotiating a TLS session over it when
+/// `tls` is set.
+async fn connect(
+    address: &str,
+    tls: Option<&tokio_native_tls::TlsConnector>,
+) -> crate::Result<MaybeTlsStream> {
+    let tcp = TcpStream::connect(address).await?;
+    match tls {
+        Some(connector) => {
+            let tls_stream = connector.connect(address_domain(address), tcp).await?;
+            Ok(MaybeTlsStream::Tls(tls_stream))
+        }
+        None => Ok(MaybeTlsStream::Tcp(tcp)),
+    }
+}
+
+/// Drives one sink's connection for its whole lifetime: connects (retrying
+/// with backoff on failure), replays any spooled frames ahead of new events
+/// once connected, and spools (or drops, if disabled) a frame that can't be
+/// written before the connection goes away again.
+async fn run(
+    address: String,
+    tls: Option<tokio_native_tls::TlsConnector>,
+    mut events: mpsc::UnboundedReceiver<Event>,
+    encode_event: impl Fn(Event) -> Option<Bytes> + Send + Sync + 'static,
+    encryptor: Option<FrameEncryptor>,
+    backoff: ReconnectBackoffConfig,
+    mut spool: Option<DiskSpool>,
+) {
+    let mut reconnect = ReconnectState::new(backoff);
+
+    'connect: loop {
+        let mut stream = match connect(&address, tls.as_ref()).await {
+            Ok(stream) => stream,
+            Err(_) => {
+                tokio::time::sleep(reconnect.next_delay()).await;
+                continue 'connect;
+            }
+        };
+        reconnect.reset();
+
+        if let Some(spool) = spool.as_mut() {
+            if !replay(spool, |frame| stream.write_all(frame)).await {
+                continue 'connect;
+            }
+        }
+
+        loop {
+            let event = match events.recv().await {
+                Some(event) => event,
+                None => return,
+            };
+
+            let frame = match frame_event(&encode_event, &encryptor, event) {
+                Some(frame) => frame,
+                None => continue,
+            };
+
+            if stream.write_all(&frame).await.is_err() {
+                if let Some(spool) = spool.as_mut() {
+                    requeue(spool, std::iter::once(frame));
+                }
+                continue 'connect;
+            }
+        }
+    }
+}
+
+/// Drains `spool` and replays its frames (oldest first) through `write`.
+/// `drain` already removed every one of them from disk, so on the first
+/// failure the failed frame and everything still unreplayed behind it are
+/// pushed back onto the spool rather than lost. Returns whether every frame
+/// was written successfully.
+async fn replay<F, Fut>(spool: &mut DiskSpool, mut write: F) -> bool
+where
+    F: FnMut(&[u8]) -> Fut,
+    Fut: std::future::Future<Output = std::io::Result<()>>,
+{
+    let frames = match spool.drain() {
+        Ok(frames) => frames,
+        Err(error) => {
+            tracing::warn!(message = "Failed to drain disk spool.", %error);
+            return true;
+        }
+    };
+
+    let mut frames = frames.into_iter();
+    while let Some(frame) = frames.next() {
+        if write(&frame).await.is_err() {
+            requeue(spool, std::iter::once(frame).chain(frames));
+            return false;
+        }
+    }
+    true
+}
+
+/// Pushes each of `frames` back onto `spool`, in order, logging (rather than
+/// aborting on) any individual push failure so one bad frame can't strand the
+/// rest behind it.
+fn requeue(spool: &mut DiskSpool, frames: impl Iterator<Item = Vec<u8>>) {
+    for frame in frames {
+        if let Err(error) = spool.push(&frame) {
+            tracing::warn!(message = "Failed to spool undeliverable frame.", %error);
+        }
+    }
+}
+
+/// Encodes `event`, optionally AEAD-encrypts the result, then appends the
+/// newline delimiter -- always outside any encrypted frame.
+fn frame_event(
+    encode_event: &(impl Fn(Event) -> Option<Bytes> + Send + Sync + 'static),
+    encryptor: &Option<FrameEncryptor>,
+    event: Event,
+) -> Option<Vec<u8>> {
+    let mut frame = encode_event(event)?.to_vec();
+    if let Some(encryptor) = encryptor {
+        frame = encryptor.encrypt(&frame);
+    }
+    frame.push(b'\n');
+    Some(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key() -> String {
+        "0".repeat(64)
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let encryptor = EncryptionConfig {
+            key: Some(test_key()),
+            key_file: None,
+        }
+        .build()
+        .unwrap();
+
+        let plaintext = b"<14>1 2021-01-01T00:00:00.000Z host app 123 - - hello";
+        let encrypted = encryptor.encrypt(plaintext);
+
+        // nonce (12) + ciphertext + tag (16)
+        assert_eq!(encrypted.len(), 12 + plaintext.len() + 16);
+
+        let decrypted = encryptor.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn encrypt_is_nonce_randomized() {
+        let encryptor = EncryptionConfig {
+            key: Some(test_key()),
+            key_file: None,
+        }
+        .build()
+        .unwrap();
+
+        let a = encryptor.encrypt(b"same frame");
+        let b = encryptor.encrypt(b"same frame");
+        assert_ne!(a, b, "each frame must use a fresh random nonce");
+    }
+
+    #[test]
+    fn decrypt_rejects_tampered_frame() {
+        let encryptor = EncryptionConfig {
+            key: Some(test_key()),
+            key_file: None,
+        }
+        .build()
+        .unwrap();
+
+        let mut encrypted = encryptor.encrypt(b"hello");
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0xFF;
+
+        assert!(encryptor.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn load_key_rejects_both_key_and_key_file() {
+        let err = EncryptionConfig {
+            key: Some(test_key()),
+            key_file: Some(PathBuf::from("/dev/null")),
+        }
+        .build();
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn address_domain_strips_the_port() {
+        assert_eq!(
+            address_domain("logs.papertrailapp.com:12345"),
+            "logs.papertrailapp.com"
+        );
+        assert_eq!(address_domain("localhost:1"), "localhost");
+    }
+
+    fn test_backoff() -> ReconnectBackoffConfig {
+        ReconnectBackoffConfig {
+            initial_backoff_secs: 1.0,
+            max_backoff_secs: 10.0,
+            multiplier: 2.0,
+            jitter_factor: 0.0,
+        }
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_then_caps() {
+        let backoff = test_backoff();
+        assert_eq!(backoff.delay_for_attempt(0), Duration::from_secs_f64(1.0));
+        assert_eq!(backoff.delay_for_attempt(1), Duration::from_secs_f64(2.0));
+        assert_eq!(backoff.delay_for_attempt(2), Duration::from_secs_f64(4.0));
+        // 1.0 * 2^5 = 32, capped to max_backoff_secs
+        assert_eq!(backoff.delay_for_attempt(5), Duration::from_secs_f64(10.0));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_within_bounds() {
+        let backoff = ReconnectBackoffConfig {
+            jitter_factor: 0.5,
+            ..test_backoff()
+        };
+
+        for _ in 0..100 {
+            let delay = backoff.delay_for_attempt(1).as_secs_f64();
+            assert!((1.0..=3.0).contains(&delay), "delay {} out of bounds", delay);
+        }
+    }
+
+    #[test]
+    fn reconnect_state_resets_attempt_counter() {
+        let mut state = ReconnectState::new(test_backoff());
+
+        assert_eq!(state.next_delay(), Duration::from_secs_f64(1.0));
+        assert_eq!(state.next_delay(), Duration::from_secs_f64(2.0));
+
+        state.reset();
+        assert_eq!(state.next_delay(), Duration::from_secs_f64(1.0));
+    }
+
+    fn spool_config(dir: &std::path::Path) -> DiskSpoolConfig {
+        DiskSpoolConfig {
+            directory: dir.to_path_buf(),
+            max_size_bytes: 64 * 1024 * 1024,
+            ttl_secs: 24 * 60 * 60,
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_writes_every_frame_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spool = DiskSpool::open(&spool_config(dir.path())).unwrap();
+        spool.push(b"first").unwrap();
+        spool.push(b"second").unwrap();
+
+        let mut written = Vec::new();
+        let ok = replay(&mut spool, |frame| {
+            written.push(frame.to_vec());
+            async { Ok(()) }
+        })
+        .await;
+
+        assert!(ok);
+        assert_eq!(written, vec![b"first".to_vec(), b"second".to_vec()]);
+        assert!(spool.drain().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_requeues_the_failed_frame_and_everything_behind_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spool = DiskSpool::open(&spool_config(dir.path())).unwrap();
+        spool.push(b"first").unwrap();
+        spool.push(b"second").unwrap();
+        spool.push(b"third").unwrap();
+
+        let mut attempt = 0;
+        let ok = replay(&mut spool, |_frame| {
+            attempt += 1;
+            // Fails to write the second frame onward, simulating the
+            // connection dropping mid-replay.
+            let succeeds = attempt == 1;
+            async move {
+                if succeeds {
+                    Ok(())
+                } else {
+                    Err(std::io::Error::new(std::io::ErrorKind::Other, "write failed"))
+                }
+            }
+        })
+        .await;
+
+        assert!(!ok);
+        // `drain` already removed all three from disk before replay started,
+        // so without requeuing, "second" and "third" would be gone for good.
+        assert_eq!(
+            spool.drain().unwrap(),
+            vec![b"second".to_vec(), b"third".to_vec()]
+        );
+    }
+}
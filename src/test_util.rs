@@ -0,0 +1,10 @@
+use crate::config::GenerateConfig;
+
+/// Verifies a component's `generate_config` output actually parses back into itself.
+pub fn test_generate_config<T>()
+where
+    T: GenerateConfig + serde::de::DeserializeOwned,
+{
+    let serialized = toml::to_string(&T::generate_config()).unwrap();
+    toml::from_str::<T>(&serialized).unwrap();
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// TLS options shared by every TCP-based sink/source.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub enabled: Option<bool>,
+    pub ca_file: Option<String>,
+    pub crt_file: Option<String>,
+    pub key_file: Option<String>,
+}
+
+impl TlsConfig {
+    /// A config with TLS turned on and no custom certificates, i.e. verify against
+    /// the platform's trusted roots.
+    pub fn enabled() -> Self {
+        TlsConfig {
+            enabled: Some(true),
+            ..Default::default()
+        }
+    }
+
+    /// Builds a connector from these settings, or `None` if TLS isn't enabled,
+    /// so callers can do `if let Some(connector) = tls.connector()? { ... }`
+    /// around an otherwise-plaintext connect step.
+    pub fn connector(&self) -> crate::Result<Option<tokio_native_tls::TlsConnector>> {
+        if !self.enabled.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let mut builder = native_tls::TlsConnector::builder();
+
+        if let Some(ca_file) = &self.ca_file {
+            let mut pem = Vec::new();
+            std::fs::File::open(ca_file)?.read_to_end(&mut pem)?;
+            builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+        }
+
+        match (&self.crt_file, &self.key_file) {
+            (Some(crt_file), Some(key_file)) => {
+                let crt = std::fs::read(crt_file)?;
+                let key = std::fs::read(key_file)?;
+                builder.identity(native_tls::Identity::from_pkcs8(&crt, &key)?);
+            }
+            (None, None) => {}
+            _ => return Err("`crt_file` and `key_file` must be set together".into()),
+        }
+
+        Ok(Some(tokio_native_tls::TlsConnector::from(builder.build()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connector_is_none_when_disabled() {
+        assert!(TlsConfig::default().connector().unwrap().is_none());
+    }
+
+    #[test]
+    fn connector_is_some_when_enabled() {
+        assert!(TlsConfig::enabled().connector().unwrap().is_some());
+    }
+
+    #[test]
+    fn connector_rejects_crt_file_without_key_file() {
+        let tls = TlsConfig {
+            crt_file: Some("cert.pem".into()),
+            ..TlsConfig::enabled()
+        };
+        assert!(tls.connector().is_err());
+    }
+}